@@ -1,9 +1,15 @@
-use std::time::{Duration, Instant};
+use std::time::Instant;
 
-use ggez::event::{self, KeyCode};
-use ggez::graphics::{self, mint::Point2};
+use ggez::conf::FullscreenType;
+use ggez::event::{self, KeyCode, MouseButton};
+use ggez::graphics::{self, mint::Point2, Rect};
 use ggez::{Context, GameResult};
 
+use ggez_egui::EguiBackend;
+
+mod tween;
+use tween::{interpolate, Tween};
+
 
 // Here we define the size of our game board in terms of how many grid
 // cells it will take up. We choose to make a 30 x 20 game board.
@@ -24,8 +30,11 @@ const SCREEN_SIZE: (u32, u32) = (800, 400);
 // important later so that we don't have our snake fly across the screen because
 // it's moving a full tile every frame.
 const UPDATES_PER_SECOND: f32 = 10.0;
-// And we get the milliseconds of delay that this update rate corresponds to.
-const MILLIS_PER_UPDATE: u64 = (1.0 / UPDATES_PER_SECOND * 1000.0) as u64;
+
+// How many circles the batched-rendering demo spawns. Drawing this many through
+// individual `graphics::draw` calls each frame is exactly the slow path the
+// `MeshBatch` below is meant to replace.
+const INSTANCE_COUNT: usize = 1000;
 
 
 // This is a trait that provides a modulus function that works for negative values
@@ -49,53 +58,361 @@ where
     }
 }
 
+// How the fixed logical render target is fitted into the actual window.
+#[derive(Clone, Copy, PartialEq, Eq)]
+enum ScaleMode {
+    // Fill the whole window, distorting the aspect ratio if it doesn't match.
+    Stretch,
+    // Scale uniformly to the largest size that fits, centred with black bars.
+    Letterbox,
+    // Like `Letterbox` but snapped down to the largest whole-number multiple, so
+    // every logical pixel maps to an exact square block of screen pixels.
+    PixelPerfect,
+}
+
+impl ScaleMode {
+    // Step to the next mode, wrapping back to the start. Bound to a key so the modes
+    // can be compared live.
+    fn next(self) -> ScaleMode {
+        match self {
+            ScaleMode::Stretch => ScaleMode::Letterbox,
+            ScaleMode::Letterbox => ScaleMode::PixelPerfect,
+            ScaleMode::PixelPerfect => ScaleMode::Stretch,
+        }
+    }
+}
+
 struct MainState {
     pos_x: f32,
+    // The position the circle held *before* the latest fixed step. `draw` blends
+    // between `prev_pos_x` and `pos_x` so motion stays smooth between updates.
+    prev_pos_x: f32,
     offset_x: f32,
-    last_update: Instant,
+    // The radius of the circle. This used to be hardcoded to `100.0` in `draw`,
+    // but the egui overlay now lets us tweak it live.
+    radius: f32,
+    // How many times per second we advance the simulation. Kept as a field so the
+    // overlay slider can retune the update rate without a recompile.
+    updates_per_second: f32,
+    // Leftover real time carried between frames. When it reaches `dt` we run one
+    // fixed step; see the "Fix Your Timestep" loop in `update`.
+    accumulator: f32,
+    // The easing curve that carries the circle from its current spot to its next
+    // target. Direction presses retarget it; the fixed step advances its `t`.
+    tween: Tween,
+    // Whether we are presently running fullscreen (toggled with `F`).
+    is_fullscreen: bool,
+    // The resolutions reported by the current monitor, plus an index into them that
+    // the up/down arrows walk through.
+    resolutions: Vec<(f32, f32)>,
+    resolution_index: usize,
+    // The circle mesh, built once and reused every frame. `draw` only updates the
+    // `DrawParam` translation instead of rebuilding a `MeshBuilder` per frame.
+    // `mesh_radius` records the radius it was built at so we can rebuild it — and
+    // only then — when the overlay slider changes the size.
+    circle_mesh: graphics::Mesh,
+    mesh_radius: f32,
+    // The batched-rendering demo: when `batched` is set we draw `INSTANCE_COUNT`
+    // circles through a single `MeshBatch` instead of one draw call each, and show
+    // how long that draw took. `instances` holds each circle's x position and
+    // `instance_ids` the matching `MeshIdx` handles returned by `batch.add`.
+    batched: bool,
+    batch: graphics::MeshBatch,
+    instances: Vec<f32>,
+    instance_ids: Vec<graphics::MeshIdx>,
+    draw_time: std::time::Duration,
+    // The fixed internal resolution the simulation is rendered at, independent of
+    // the window size. The circle's motion lives entirely in these coordinates.
+    logical_size: (f32, f32),
+    // The offscreen render target sized at `logical_size`; the scene is drawn into
+    // it and then blitted to the window according to `scale_mode`.
+    canvas: graphics::Canvas,
+    // How that blit fits the target into the window (cycled with `S`).
+    scale_mode: ScaleMode,
+    // The immediate-mode egui overlay that drives our live tweaking controls.
+    egui_backend: EguiBackend,
+}
+
+// Build the circle mesh centred on the origin. Keeping it at the origin lets us
+// position it purely through the `DrawParam` destination, so the same mesh serves
+// both the single circle and every instance in the batch.
+fn build_circle_mesh(ctx: &mut Context, radius: f32) -> GameResult<graphics::Mesh> {
+    graphics::MeshBuilder::new()
+        .circle(
+            graphics::DrawMode::fill(),
+            Point2::from([0.0, 0.0]),
+            radius,
+            0.1,
+            [0.0, 0.0, 1.0, 1.0].into(),
+        )?
+        .build(ctx)
 }
 
 impl MainState {
-    fn new(_ctx: &mut Context) -> GameResult<MainState> {
-        Ok(MainState { 
+    fn new(ctx: &mut Context) -> GameResult<MainState> {
+        // Ask the current monitor for the resolutions it supports so the up/down arrows
+        // have something to cycle through. If the query comes back empty (e.g. a headless
+        // setup) we fall back to the logical board size.
+        let mut resolutions: Vec<(f32, f32)> = graphics::window(ctx)
+            .current_monitor()
+            .map(|monitor| {
+                monitor
+                    .video_modes()
+                    .map(|mode| {
+                        let size = mode.size();
+                        (size.width as f32, size.height as f32)
+                    })
+                    .collect()
+            })
+            .unwrap_or_default();
+        if resolutions.is_empty() {
+            resolutions.push((SCREEN_SIZE.0 as f32, SCREEN_SIZE.1 as f32));
+        }
+
+        // Build the reusable circle mesh once, and seed the batch with one instance
+        // per demo circle spread evenly down the board.
+        let circle_mesh = build_circle_mesh(ctx, 100.0)?;
+        let mut batch = graphics::MeshBatch::new(circle_mesh.clone())?;
+        let mut instances = Vec::with_capacity(INSTANCE_COUNT);
+        let mut instance_ids = Vec::with_capacity(INSTANCE_COUNT);
+        for i in 0..INSTANCE_COUNT {
+            let y = (i as f32 * 7.0).modulo(SCREEN_SIZE.1 as f32);
+            instances.push(0.0);
+            instance_ids.push(batch.add(graphics::DrawParam::default().dest([0.0, y])));
+        }
+
+        // The offscreen target the scene is rendered into, fixed at the logical size.
+        let canvas = graphics::Canvas::new(
+            ctx,
+            SCREEN_SIZE.0 as u16,
+            SCREEN_SIZE.1 as u16,
+            ggez::conf::NumSamples::One,
+            graphics::get_window_color_format(ctx),
+        )?;
+
+        Ok(MainState {
             pos_x: 0.0,
+            prev_pos_x: 0.0,
             offset_x: 10.0,
-            last_update: Instant::now(),
+            radius: 100.0,
+            updates_per_second: UPDATES_PER_SECOND,
+            accumulator: 0.0,
+            // Rest at the origin until a direction key retargets us. One second of
+            // travel on the smooth cubic S-curve feels right for the sandbox.
+            tween: Tween::new(0.0, 0.0, 1.0, tween::cubic_in_out),
+            is_fullscreen: false,
+            resolutions,
+            resolution_index: 0,
+            circle_mesh,
+            mesh_radius: 100.0,
+            batched: false,
+            batch,
+            instances,
+            instance_ids,
+            draw_time: std::time::Duration::ZERO,
+            logical_size: (SCREEN_SIZE.0 as f32, SCREEN_SIZE.1 as f32),
+            canvas,
+            scale_mode: ScaleMode::Letterbox,
+            egui_backend: EguiBackend::default(),
         })
     }
+
+    // Work out how to fit the logical target into the current window for the active
+    // scale mode: the per-axis scale factor and the top-left offset that centres it.
+    fn blit_transform(&self, window: (f32, f32)) -> ([f32; 2], [f32; 2]) {
+        let (lw, lh) = self.logical_size;
+        let (ww, wh) = window;
+        match self.scale_mode {
+            ScaleMode::Stretch => ([ww / lw, wh / lh], [0.0, 0.0]),
+            ScaleMode::Letterbox => {
+                let scale = (ww / lw).min(wh / lh);
+                let offset = [(ww - lw * scale) / 2.0, (wh - lh * scale) / 2.0];
+                ([scale, scale], offset)
+            }
+            ScaleMode::PixelPerfect => {
+                let scale = (ww / lw).min(wh / lh).floor().max(1.0);
+                let offset = [(ww - lw * scale) / 2.0, (wh - lh * scale) / 2.0];
+                ([scale, scale], offset)
+            }
+        }
+    }
+
+    // Switch to the resolution at `resolution_index` and resize the window to match.
+    // Fitting the fixed logical target into the new window size is left entirely to
+    // the scaling subsystem in `draw`.
+    fn apply_resolution(&mut self, ctx: &mut Context) -> GameResult<()> {
+        let (width, height) = self.resolutions[self.resolution_index];
+        graphics::set_drawable_size(ctx, width, height)?;
+        Ok(())
+    }
+
+    // Aim the tween one `offset_x` hop away from where the circle currently sits,
+    // restarting the easing curve. Both the arrow keys and the overlay buttons go
+    // through here so their behaviour stays identical.
+    fn hop(&mut self) {
+        let target = interpolate(&self.tween) + self.offset_x;
+        self.tween.retarget(target);
+    }
+
+    // Draw the simulation itself. This always runs in logical coordinates; the caller
+    // is responsible for pointing it at the offscreen target first.
+    fn draw_scene(&mut self, ctx: &mut Context) -> GameResult<()> {
+        graphics::clear(ctx, [0.0, 0.0, 0.0, 1.0].into());
+
+        // The circle mesh is cached, so we only pay to rebuild it when the overlay
+        // slider actually changes the radius — not once per frame.
+        if self.radius != self.mesh_radius {
+            self.circle_mesh = build_circle_mesh(ctx, self.radius)?;
+            self.batch = graphics::MeshBatch::new(self.circle_mesh.clone())?;
+            self.instance_ids.clear();
+            for (i, x) in self.instances.iter().enumerate() {
+                let y = (i as f32 * 7.0).modulo(self.logical_size.1);
+                self.instance_ids
+                    .push(self.batch.add(graphics::DrawParam::default().dest([*x, y])));
+            }
+            self.mesh_radius = self.radius;
+        }
+
+        // Render at the interpolated position so motion is smooth regardless of how the
+        // fixed update rate lines up with the display's frame rate.
+        let dt = 1.0 / self.updates_per_second;
+        let alpha = self.accumulator / dt;
+        // Blend in unwrapped space, then wrap the result, so motion stays continuous
+        // even on the step that crosses the right edge of the board.
+        let render_x = (self.prev_pos_x * (1.0 - alpha) + self.pos_x * alpha)
+            .modulo(self.logical_size.0);
+
+        if self.batched {
+            // Batched path: refresh every instance's translation and flush the whole
+            // lot through a single `MeshBatch` draw.
+            for (i, x) in self.instances.iter().enumerate() {
+                let y = (i as f32 * 7.0).modulo(self.logical_size.1);
+                self.batch
+                    .set(self.instance_ids[i], graphics::DrawParam::default().dest([*x, y]))?;
+            }
+            self.batch.flush(ctx)?;
+            graphics::draw(ctx, &self.batch, graphics::DrawParam::default())?;
+        } else {
+            // Cached single circle: reuse the stored mesh and only move it.
+            graphics::draw(
+                ctx,
+                &self.circle_mesh,
+                graphics::DrawParam::default().dest([render_x, self.logical_size.1 / 2.0]),
+            )?;
+        }
+
+        // Report the previous frame's measured time so the performance gap is visible at
+        // a glance. The measurement itself happens in `draw` around `present`, since that
+        // is where the GPU actually does the work recorded by the draw calls above.
+        let label = graphics::Text::new(format!(
+            "frame: {:.3} ms ({})",
+            self.draw_time.as_secs_f64() * 1000.0,
+            if self.batched { "batched" } else { "single" }
+        ));
+        graphics::draw(ctx, &label, graphics::DrawParam::default().dest([10.0, 10.0]))?;
+        Ok(())
+    }
 }
 
 impl event::EventHandler for MainState {
-    fn update(&mut self, _ctx: &mut Context) -> GameResult<()> {
-        // First we check to see if enough time has elapsed since our last update based on
-        // the update rate we defined at the top.
-        let now = Instant::now();
-        if now - self.last_update >= Duration::from_millis(MILLIS_PER_UPDATE) {
-            // we update the state
-            self.pos_x = self.pos_x.modulo(SCREEN_SIZE.0 as f32) + self.offset_x;
-            
-            // If we updated, we set our last_update to be now
-            self.last_update = now;
+    fn update(&mut self, ctx: &mut Context) -> GameResult<()> {
+        // We lay out the egui panel every frame so its widgets reflect (and mutate)
+        // the live state. egui is immediate-mode, so building the UI *is* the update.
+        let egui_ctx = self.egui_backend.ctx();
+        egui::Window::new("sandbox").show(&egui_ctx, |ui| {
+            ui.add(egui::Slider::new(&mut self.offset_x, -100.0..=100.0).text("offset_x"));
+            ui.add(egui::Slider::new(&mut self.radius, 1.0..=300.0).text("radius"));
+            ui.add(
+                egui::Slider::new(&mut self.updates_per_second, 1.0..=120.0).text("updates/s"),
+            );
+            ui.horizontal(|ui| {
+                if ui.button("left").clicked() {
+                    self.offset_x = -self.offset_x.abs();
+                    self.hop();
+                }
+                if ui.button("right").clicked() {
+                    self.offset_x = self.offset_x.abs();
+                    self.hop();
+                }
+            });
+        });
+
+        // The size of a single fixed step, in seconds. The update rate can be changed
+        // at runtime, so we derive `dt` from the current `updates_per_second`.
+        let dt = 1.0 / self.updates_per_second;
+
+        // We feed the *real* elapsed time into the accumulator, clamping it so a hitch
+        // (e.g. the window being dragged) can't make us run an unbounded number of steps
+        // and fall into the "spiral of death".
+        let frame_time = ggez::timer::delta(ctx).as_secs_f32().min(0.25);
+        self.accumulator += frame_time;
+
+        // Drain the accumulator one fixed step at a time.
+        while self.accumulator >= dt {
+            // Snapshot the pre-step position so `draw` can interpolate toward the new one.
+            // This has to happen per *step*, not per frame: on the many frames that run no
+            // steps `prev_pos_x`/`pos_x` must persist unchanged so `alpha` keeps blending.
+            self.prev_pos_x = self.pos_x;
+
+            // Advance the easing curve and read off where the circle should sit. We keep
+            // `pos_x` in unwrapped space here; the wraparound is applied in `draw` after
+            // interpolation so a step crossing the board edge doesn't sweep backwards.
+            self.tween.t += dt;
+            self.pos_x = interpolate(&self.tween);
+
+            // Drift the demo instances along too, each at a slightly different speed so
+            // the batch looks alive rather than a rigid column.
+            if self.batched {
+                let width = self.logical_size.0;
+                for (i, x) in self.instances.iter_mut().enumerate() {
+                    let speed = self.offset_x * (1.0 + i as f32 / INSTANCE_COUNT as f32);
+                    *x = (*x + speed).modulo(width);
+                }
+            }
+            self.accumulator -= dt;
         }
         // Finally we return `Ok` to indicate we didn't run into any errors
         Ok(())
     }
 
     fn draw(&mut self, ctx: &mut Context) -> GameResult<()> {
+        // Time the whole frame, up to and including `present`. `graphics::draw` only
+        // records commands; the GPU work — and thus the real gap between the single and
+        // batched paths — is flushed by `present`, so that is what we have to measure.
+        let frame_start = Instant::now();
+
+        // First render the whole simulation into the fixed-size offscreen target, in
+        // logical coordinates, so it is resolution-independent.
+        graphics::set_canvas(ctx, Some(&self.canvas));
+        graphics::set_screen_coordinates(
+            ctx,
+            Rect::new(0.0, 0.0, self.logical_size.0, self.logical_size.1),
+        )?;
+        self.draw_scene(ctx)?;
+
+        // Back to the window's backbuffer. Clear it to black so the letterbox bars show,
+        // and restore the coordinate system to window pixels for the blit.
+        graphics::set_canvas(ctx, None);
+        let window = graphics::drawable_size(ctx);
+        graphics::set_screen_coordinates(ctx, Rect::new(0.0, 0.0, window.0, window.1))?;
         graphics::clear(ctx, [0.0, 0.0, 0.0, 1.0].into());
-        let mesh = graphics::MeshBuilder::new()
-            .circle(
-                graphics::DrawMode::fill(),
-                Point2::from([self.pos_x, SCREEN_SIZE.1 as f32 / 2.0]),
-                100.0,
-                0.1,
-                [0.0, 0.0, 1.0, 1.0].into()
-            )?
-            .build(ctx)?;
-        graphics::draw(ctx, &mesh, graphics::DrawParam::default())?;
+
+        // Blit the logical target into the window with the scale/offset for the mode.
+        let (scale, offset) = self.blit_transform(window);
+        graphics::draw(
+            ctx,
+            &self.canvas,
+            graphics::DrawParam::default().scale(scale).dest(offset),
+        )?;
+
+        // The egui overlay is drawn on top in window space so its controls stay crisp
+        // and clickable regardless of the scaling mode.
+        graphics::draw(ctx, &self.egui_backend, graphics::DrawParam::default())?;
         // Finally we call graphics::present to cycle the gpu's framebuffer and display
         // the new frame we just drew.
         graphics::present(ctx)?;
+        // Record how long the frame took so next frame's counter can display it.
+        self.draw_time = frame_start.elapsed();
         // We yield the current thread until the next update
         ggez::timer::yield_now();
         // And return success.
@@ -112,11 +429,55 @@ impl event::EventHandler for MainState {
     ) {
         match keycode {
             KeyCode::Escape => event::quit(ctx),
-            KeyCode::Left  => { self.offset_x = -self.offset_x.abs() },
-            KeyCode::Right => { self.offset_x = self.offset_x.abs() },
+            KeyCode::Left  => { self.offset_x = -self.offset_x.abs(); self.hop(); },
+            KeyCode::Right => { self.offset_x = self.offset_x.abs(); self.hop(); },
+            // F toggles between windowed and (desktop) fullscreen.
+            KeyCode::F => {
+                self.is_fullscreen = !self.is_fullscreen;
+                let fullscreen_type = if self.is_fullscreen {
+                    FullscreenType::Desktop
+                } else {
+                    FullscreenType::Windowed
+                };
+                let _ = graphics::set_fullscreen(ctx, fullscreen_type);
+            }
+            // Up/Down walk through the monitor's supported resolutions, wrapping around.
+            KeyCode::Up => {
+                self.resolution_index = (self.resolution_index + 1) % self.resolutions.len();
+                let _ = self.apply_resolution(ctx);
+            }
+            KeyCode::Down => {
+                let len = self.resolutions.len();
+                self.resolution_index = (self.resolution_index + len - 1) % len;
+                let _ = self.apply_resolution(ctx);
+            }
+            // B flips between the single cached circle and the batched instance demo.
+            KeyCode::B => { self.batched = !self.batched; }
+            // S cycles the screen-scaling mode (stretch / letterbox / pixel-perfect).
+            KeyCode::S => { self.scale_mode = self.scale_mode.next(); }
             _ => (),
         };
     }
+
+    // The next four handlers forward pointer and text input into the egui backend
+    // so the overlay's widgets stay interactive.
+    fn mouse_motion_event(&mut self, _ctx: &mut Context, x: f32, y: f32, _dx: f32, _dy: f32) {
+        self.egui_backend.input.mouse_motion_event(x, y);
+    }
+
+    fn mouse_button_down_event(&mut self, _ctx: &mut Context, button: MouseButton, _x: f32, _y: f32) {
+        self.egui_backend.input.mouse_button_down_event(button);
+    }
+
+    fn mouse_button_up_event(&mut self, _ctx: &mut Context, button: MouseButton, _x: f32, _y: f32) {
+        // egui only emits a click on press *and* release, so the overlay buttons need
+        // the release forwarded too or they never fire.
+        self.egui_backend.input.mouse_button_up_event(button);
+    }
+
+    fn text_input_event(&mut self, _ctx: &mut Context, character: char) {
+        self.egui_backend.input.text_input_event(character);
+    }
 }
 
 fn main() -> GameResult {
@@ -0,0 +1,108 @@
+//! A tiny tweening helper for smoothly animating a single `f32` between two
+//! values. It is deliberately minimal: a [`Tween`] remembers where it started,
+//! where it is heading, how long the trip takes and which easing curve to apply,
+//! and [`interpolate`] samples the current value for the elapsed time `t`.
+//!
+//! The easing curves are the usual in/out family (linear, quadratic, cubic),
+//! expressed as plain function pointers over a normalized `0..=1` parameter so
+//! callers can swap them freely — including the `cubic_in_out` S-curve that the
+//! sandbox uses by default.
+
+// The sandbox only wires up `cubic_in_out` today, but the full easing family is
+// part of this subsystem's public surface, so we keep the curves and silence the
+// dead-code lint rather than trimming them.
+#![allow(dead_code)]
+
+/// An easing function mapping a normalized parameter in `0..=1` to an eased
+/// value in (roughly) the same range. Stored in a [`Tween`] as a function
+/// pointer so the curve can be chosen at runtime.
+pub type EaseFn = fn(f32) -> f32;
+
+/// No easing: the parameter passes straight through.
+pub fn linear(t: f32) -> f32 {
+    t
+}
+
+/// Quadratic ease-in: starts slow, accelerates.
+pub fn quadratic_in(t: f32) -> f32 {
+    t * t
+}
+
+/// Quadratic ease-out: starts fast, decelerates.
+pub fn quadratic_out(t: f32) -> f32 {
+    t * (2.0 - t)
+}
+
+/// Quadratic ease-in-out: accelerates through the first half, decelerates
+/// through the second.
+pub fn quadratic_in_out(t: f32) -> f32 {
+    if t < 0.5 {
+        2.0 * t * t
+    } else {
+        let t = t - 1.0;
+        1.0 - 2.0 * t * t
+    }
+}
+
+/// Cubic ease-in: a steeper slow start than the quadratic variant.
+pub fn cubic_in(t: f32) -> f32 {
+    t * t * t
+}
+
+/// Cubic ease-out: a steeper settle than the quadratic variant.
+pub fn cubic_out(t: f32) -> f32 {
+    let t = t - 1.0;
+    t * t * t + 1.0
+}
+
+/// Cubic ease-in-out: the smooth S-curve this sandbox animates the circle with.
+pub fn cubic_in_out(t: f32) -> f32 {
+    if t < 0.5 {
+        4.0 * t * t * t
+    } else {
+        let t = 2.0 * t - 2.0;
+        0.5 * t * t * t + 1.0
+    }
+}
+
+/// A single scalar animation from `start` to `end` over `duration` seconds,
+/// shaped by `ease`. `t` is the elapsed time; it is the caller's job to advance
+/// it (typically by the fixed timestep each update).
+pub struct Tween {
+    pub start: f32,
+    pub end: f32,
+    pub t: f32,
+    pub duration: f32,
+    pub ease: EaseFn,
+}
+
+impl Tween {
+    /// Create a tween that will travel from `start` to `end` over `duration`
+    /// seconds using the given easing curve, beginning at `t = 0`.
+    pub fn new(start: f32, end: f32, duration: f32, ease: EaseFn) -> Tween {
+        Tween {
+            start,
+            end,
+            t: 0.0,
+            duration,
+            ease,
+        }
+    }
+
+    /// Aim the tween at a new `end`, starting over from the current value so the
+    /// motion continues smoothly from wherever the shape currently sits.
+    pub fn retarget(&mut self, end: f32) {
+        self.start = interpolate(self);
+        self.end = end;
+        self.t = 0.0;
+    }
+}
+
+/// Sample the tween at its current `t`, returning the eased value between
+/// `start` and `end`. The normalized parameter is clamped to `0..=1` so the
+/// result never overshoots even if `t` runs past `duration`.
+pub fn interpolate(tween: &Tween) -> f32 {
+    let normalized = (tween.t / tween.duration).clamp(0.0, 1.0);
+    let eased = (tween.ease)(normalized);
+    tween.start + (tween.end - tween.start) * eased
+}